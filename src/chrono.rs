@@ -1,4 +1,4 @@
-use chrono::NaiveDate;
+use chrono::{Datelike, Days, NaiveDate, NaiveDateTime};
 
 use crate::PaymentDate;
 
@@ -6,4 +6,87 @@ impl PaymentDate for NaiveDate {
     fn days_since(self, other: Self) -> i32 {
         (self - other).num_days() as i32
     }
+
+    fn year(self) -> i32 {
+        Datelike::year(&self)
+    }
+
+    fn month(self) -> i32 {
+        Datelike::month(&self) as i32
+    }
+
+    fn day(self) -> i32 {
+        Datelike::day(&self) as i32
+    }
+
+    fn add_days(self, days: i32) -> Self {
+        if days >= 0 {
+            self.checked_add_days(Days::new(days as u64))
+        } else {
+            self.checked_sub_days(Days::new((-days) as u64))
+        }
+        .expect("date arithmetic out of range")
+    }
+
+    fn checked_add_months(self, months: i32) -> Self {
+        let total_months = PaymentDate::year(self) * 12 + PaymentDate::month(self) - 1 + months;
+        let year = total_months.div_euclid(12);
+        let month = total_months.rem_euclid(12) + 1;
+        let day = PaymentDate::day(self).min(days_in_month(year, month));
+        NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+            .expect("date arithmetic out of range")
+    }
+}
+
+impl PaymentDate for NaiveDateTime {
+    fn days_since(self, other: Self) -> i32 {
+        (self.date() - other.date()).num_days() as i32
+    }
+
+    fn year(self) -> i32 {
+        Datelike::year(&self)
+    }
+
+    fn month(self) -> i32 {
+        Datelike::month(&self) as i32
+    }
+
+    fn day(self) -> i32 {
+        Datelike::day(&self) as i32
+    }
+
+    fn add_days(self, days: i32) -> Self {
+        if days >= 0 {
+            self.checked_add_days(Days::new(days as u64))
+        } else {
+            self.checked_sub_days(Days::new((-days) as u64))
+        }
+        .expect("date arithmetic out of range")
+    }
+
+    fn checked_add_months(self, months: i32) -> Self {
+        PaymentDate::checked_add_months(self.date(), months).and_time(self.time())
+    }
+
+    fn fractional_days_since(self, other: Self) -> f64 {
+        let duration = self - other;
+        duration
+            .num_nanoseconds()
+            .map(|ns| ns as f64 / 86_400_000_000_000.0)
+            .unwrap_or_else(|| duration.num_seconds() as f64 / 86_400.0)
+    }
+}
+
+fn days_in_month(year: i32, month: i32) -> i32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => unreachable!(),
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
 }
@@ -1,4 +1,5 @@
-use jiff::civil::Date;
+use jiff::civil::{Date, DateTime};
+use jiff::{Span, Unit};
 
 use crate::PaymentDate;
 
@@ -6,4 +7,79 @@ impl PaymentDate for Date {
     fn days_since(self, other: Self) -> i32 {
         (self - other).get_days()
     }
+
+    fn year(self) -> i32 {
+        self.year() as i32
+    }
+
+    fn month(self) -> i32 {
+        self.month() as i32
+    }
+
+    fn day(self) -> i32 {
+        self.day() as i32
+    }
+
+    fn add_days(self, days: i32) -> Self {
+        self.checked_add(Span::new().days(days))
+            .expect("date arithmetic out of range")
+    }
+
+    fn checked_add_months(self, months: i32) -> Self {
+        let total_months = self.year() as i32 * 12 + self.month() as i32 - 1 + months;
+        let year = total_months.div_euclid(12) as i16;
+        let month = (total_months.rem_euclid(12) + 1) as i8;
+        let day = self.day().min(days_in_month(year, month));
+        Date::new(year, month, day).expect("date arithmetic out of range")
+    }
+}
+
+impl PaymentDate for DateTime {
+    fn days_since(self, other: Self) -> i32 {
+        self.date().days_since(other.date())
+    }
+
+    fn year(self) -> i32 {
+        self.year() as i32
+    }
+
+    fn month(self) -> i32 {
+        self.month() as i32
+    }
+
+    fn day(self) -> i32 {
+        self.day() as i32
+    }
+
+    fn add_days(self, days: i32) -> Self {
+        self.checked_add(Span::new().days(days))
+            .expect("date arithmetic out of range")
+    }
+
+    fn checked_add_months(self, months: i32) -> Self {
+        self.date()
+            .checked_add_months(months)
+            .to_datetime(self.time())
+    }
+
+    fn fractional_days_since(self, other: Self) -> f64 {
+        self.since(other)
+            .expect("date arithmetic out of range")
+            .total(Unit::Day)
+            .expect("date arithmetic out of range")
+    }
+}
+
+fn days_in_month(year: i16, month: i8) -> i8 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year as i32) => 29,
+        2 => 28,
+        _ => unreachable!(),
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
 }
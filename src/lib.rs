@@ -48,6 +48,19 @@ mod jiff;
 
 const MAX_ERROR: f64 = 1e-10;
 const MAX_COMPUTE_WITH_GUESS_ITERATIONS: u32 = 50;
+const MAX_BRENT_ITERATIONS: u32 = 100;
+// `1 + rate` must stay positive for the XNPV function to be real-valued, so every root lies in
+// `rate > -1`. Short-horizon cash flows with a large swing can park that root arbitrarily close
+// to -1 (`1 + rate` near zero), so the bracket scan below walks `1 + rate` geometrically rather
+// than stepping `rate` linearly: a fixed number of multiplicative steps covers the approach to -1,
+// where a fixed additive step could only ever get so close. Each step is evaluated at
+// `growth - 1.0`, which rounds to exactly -1 for `growth` below about 1e-16, so a root closer to
+// -1 than that is not found. The scan still gives up eventually and
+// returns `NaN`, e.g. for payments that net to zero regardless of rate, or whose root (if any) is
+// too far out to be worth the extra steps (see `test_max_iter`).
+const MIN_GROWTH: f64 = 1e-300;
+const MAX_GROWTH: f64 = 1e6;
+const GROWTH_STEP: f64 = 10.0;
 
 /// A payment made or received on a particular date.
 ///
@@ -62,26 +75,52 @@ pub struct Payment<T: PaymentDate> {
 ///
 /// It tries to identify the rate of return using Newton's method with an initial guess of 0.1.
 /// If that does not provide a solution, it attempts with guesses from -0.99 to 0.99
-/// in increments of 0.01 and returns NaN if that fails too.
+/// in increments of 0.01. If none of those guesses converge either, it falls back to scanning
+/// for a bracket around a root of the XNPV function, walking `1 + rate` geometrically from near
+/// zero (i.e. `rate` just above -100%) up to a very large rate, and solving it with Brent's
+/// method, which is guaranteed to converge once a bracket is found. Returns `NaN` if no root is
+/// found in that range, e.g. for payments that sum to zero regardless of rate.
+///
+/// The year fraction between payments is computed using the [`Actual365Fixed`](DayCount::Actual365Fixed)
+/// day-count convention. Use [`compute_with_convention`] to select a different one.
 ///
 /// # Errors
 ///
 /// This function will return [`InvalidPaymentsError`](struct.InvalidPaymentsError.html)
 /// if both positive and negative payments are not provided.
 pub fn compute<T: PaymentDate>(payments: &Vec<Payment<T>>) -> Result<f64, InvalidPaymentsError> {
+    compute_with_convention(payments, DayCount::Actual365Fixed)
+}
+
+/// Calculates the internal rate of return of a series of irregular payments, computing the year
+/// fraction between payments using the given day-count `convention` rather than the
+/// [`Actual365Fixed`](DayCount::Actual365Fixed) convention [`compute`] defaults to.
+///
+/// # Errors
+///
+/// This function will return [`InvalidPaymentsError`](struct.InvalidPaymentsError.html)
+/// if both positive and negative payments are not provided.
+pub fn compute_with_convention<T: PaymentDate>(
+    payments: &Vec<Payment<T>>,
+    convention: DayCount,
+) -> Result<f64, InvalidPaymentsError> {
     validate(payments)?;
 
     let mut sorted: Vec<_> = payments.iter().collect();
     sorted.sort_by_key(|p| &p.date);
 
-    let mut rate = compute_with_guess(&sorted, 0.1);
+    let mut rate = newton_with_guess(&sorted, 0.1, convention);
     let mut guess = -0.99;
 
     while guess < 1.0 && (rate.is_nan() || rate.is_infinite()) {
-        rate = compute_with_guess(&sorted, guess);
+        rate = newton_with_guess(&sorted, guess, convention);
         guess += 0.01;
     }
 
+    if rate.is_nan() || rate.is_infinite() {
+        rate = brent(&sorted, convention);
+    }
+
     Ok(rate)
 }
 
@@ -98,7 +137,35 @@ impl Display for InvalidPaymentsError {
 
 impl Error for InvalidPaymentsError {}
 
-fn compute_with_guess<T: PaymentDate>(payments: &Vec<&Payment<T>>, guess: f64) -> f64 {
+/// Calculates the internal rate of return of a series of irregular payments, seeding Newton's
+/// method with an explicit `guess` instead of sweeping from -0.99 to 0.99 as [`compute`] does.
+///
+/// This is useful when the caller already has a good estimate of the rate, e.g. from a prior
+/// period, and wants to avoid the cost of the full guess sweep. Returns `NaN` if Newton's method
+/// does not converge from `guess`; unlike [`compute`](fn.compute.html), this does not fall back
+/// to Brent's method.
+///
+/// # Errors
+///
+/// This function will return [`InvalidPaymentsError`](struct.InvalidPaymentsError.html)
+/// if both positive and negative payments are not provided.
+pub fn compute_with_guess<T: PaymentDate>(
+    payments: &Vec<Payment<T>>,
+    guess: f64,
+) -> Result<f64, InvalidPaymentsError> {
+    validate(payments)?;
+
+    let mut sorted: Vec<_> = payments.iter().collect();
+    sorted.sort_by_key(|p| &p.date);
+
+    Ok(newton_with_guess(&sorted, guess, DayCount::Actual365Fixed))
+}
+
+fn newton_with_guess<T: PaymentDate>(
+    payments: &Vec<&Payment<T>>,
+    guess: f64,
+    convention: DayCount,
+) -> f64 {
     let mut r = guess;
     let mut e = 1.0;
 
@@ -107,7 +174,7 @@ fn compute_with_guess<T: PaymentDate>(payments: &Vec<&Payment<T>>, guess: f64) -
             return r;
         }
 
-        let r1 = r - xirr(payments, r) / dxirr(payments, r);
+        let r1 = r - xirr(payments, r, convention) / dxirr(payments, r, convention);
         e = (r1 - r).abs();
         r = r1;
     }
@@ -115,24 +182,259 @@ fn compute_with_guess<T: PaymentDate>(payments: &Vec<&Payment<T>>, guess: f64) -
     f64::NAN
 }
 
-fn xirr<T: PaymentDate>(payments: &Vec<&Payment<T>>, rate: f64) -> f64 {
+/// Calculates the net present value of a series of irregular payments discounted at `rate`,
+/// relative to the date of the earliest payment.
+///
+/// This corresponds to the XNPV function found in spreadsheet applications like LibreOffice
+/// Calc. The year fraction between payments is computed using the
+/// [`Actual365Fixed`](DayCount::Actual365Fixed) day-count convention.
+pub fn xnpv<T: PaymentDate>(payments: &Vec<Payment<T>>, rate: f64) -> f64 {
+    let mut sorted: Vec<_> = payments.iter().collect();
+    sorted.sort_by_key(|p| &p.date);
+
+    xirr(&sorted, rate, DayCount::Actual365Fixed)
+}
+
+/// Calculates the modified internal rate of return of a series of irregular payments, given an
+/// explicit `finance_rate` paid on negative flows and `reinvest_rate` earned on positive flows.
+///
+/// Unlike [`compute`], which assumes every intermediate flow is reinvested at the rate it
+/// ultimately solves for, MIRR compounds each positive payment forward to the date of the last
+/// payment at `reinvest_rate`, discounts each negative payment back to the date of the first
+/// payment at `finance_rate`, and returns the single rate that equates the two.
+///
+/// # Errors
+///
+/// This function will return [`InvalidPaymentsError`](struct.InvalidPaymentsError.html) if both
+/// positive and negative payments are not provided, or if every payment falls on the same date.
+pub fn mirr<T: PaymentDate>(
+    payments: &Vec<Payment<T>>,
+    finance_rate: f64,
+    reinvest_rate: f64,
+) -> Result<f64, InvalidPaymentsError> {
+    validate(payments)?;
+
+    let mut sorted: Vec<_> = payments.iter().collect();
+    sorted.sort_by_key(|p| &p.date);
+
+    let first = sorted[0];
+    let last = sorted[sorted.len() - 1];
+    let total_years = get_exp(last, first, DayCount::Actual365Fixed);
+
+    if total_years == 0.0 {
+        return Err(InvalidPaymentsError);
+    }
+
+    let future_value: f64 = sorted
+        .iter()
+        .filter(|p| p.amount > 0.0)
+        .map(|p| {
+            let t = get_exp(p, first, DayCount::Actual365Fixed);
+            p.amount * (1.0 + reinvest_rate).powf(total_years - t)
+        })
+        .sum();
+
+    let present_value: f64 = sorted
+        .iter()
+        .filter(|p| p.amount < 0.0)
+        .map(|p| {
+            let t = get_exp(p, first, DayCount::Actual365Fixed);
+            p.amount / (1.0 + finance_rate).powf(t)
+        })
+        .sum();
+
+    Ok((future_value / -present_value).powf(1.0 / total_years) - 1.0)
+}
+
+/// How often a recurring payment in a [`generate_schedule`] repeats.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Quarterly,
+    Yearly,
+}
+
+/// When a [`generate_schedule`] stops generating payments.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ScheduleEnd<T: PaymentDate> {
+    /// Stop after generating this many payments.
+    Count(u32),
+    /// Stop once a payment would fall after this date.
+    Date(T),
+}
+
+/// Generates a series of regularly recurring payments, e.g. monthly SIPs or quarterly coupons,
+/// so callers don't have to expand them into individual [`Payment`]s by hand.
+///
+/// Payments start on `start` with the given `amount`, repeating every `interval` occurrences of
+/// `frequency` until `end` is reached. Monthly, quarterly and yearly steps clamp to the last
+/// valid day of the resulting month (e.g. Jan 31 + 1 month -> Feb 28/29).
+///
+/// Combine the result with a terminal redemption payment and pass the merged `Vec` to
+/// [`compute`].
+///
+/// # Panics
+///
+/// Panics if `interval` is zero, since that would never advance the date and, combined with
+/// [`ScheduleEnd::Date`], would loop forever.
+pub fn generate_schedule<T: PaymentDate>(
+    start: T,
+    amount: f64,
+    frequency: Frequency,
+    interval: u32,
+    end: ScheduleEnd<T>,
+) -> Vec<Payment<T>> {
+    assert!(interval > 0, "generate_schedule: interval must be greater than zero");
+
+    let mut payments = Vec::new();
+    let mut date = start;
+
+    loop {
+        match end {
+            ScheduleEnd::Count(count) if payments.len() as u32 >= count => break,
+            ScheduleEnd::Date(end_date) if date > end_date => break,
+            _ => {}
+        }
+
+        payments.push(Payment { date, amount });
+        date = step_date(date, frequency, interval);
+    }
+
+    payments
+}
+
+fn step_date<T: PaymentDate>(date: T, frequency: Frequency, interval: u32) -> T {
+    let interval = interval as i32;
+    match frequency {
+        Frequency::Daily => date.add_days(interval),
+        Frequency::Weekly => date.add_days(7 * interval),
+        Frequency::Monthly => date.checked_add_months(interval),
+        Frequency::Quarterly => date.checked_add_months(3 * interval),
+        Frequency::Yearly => date.checked_add_months(12 * interval),
+    }
+}
+
+fn xirr<T: PaymentDate>(payments: &Vec<&Payment<T>>, rate: f64, convention: DayCount) -> f64 {
     let mut result = 0.0;
     for p in payments {
-        let exp = get_exp(p, payments[0]);
+        let exp = get_exp(p, payments[0], convention);
         result += p.amount / (1.0 + rate).powf(exp)
     }
     result
 }
 
-fn dxirr<T: PaymentDate>(payments: &Vec<&Payment<T>>, rate: f64) -> f64 {
+fn dxirr<T: PaymentDate>(payments: &Vec<&Payment<T>>, rate: f64, convention: DayCount) -> f64 {
     let mut result = 0.0;
     for p in payments {
-        let exp = get_exp(p, payments[0]);
+        let exp = get_exp(p, payments[0], convention);
         result -= p.amount * exp / (1.0 + rate).powf(exp + 1.0)
     }
     result
 }
 
+// Scans upward from just above the singularity at rate = -1 for the first adjacent pair of
+// rates where the XNPV function changes sign, then refines it with Brent's method. Brent's
+// method combines the reliability of bisection with the speed of inverse quadratic/secant
+// interpolation, so unlike Newton's method it cannot diverge once a bracket is found.
+fn brent<T: PaymentDate>(payments: &Vec<&Payment<T>>, convention: DayCount) -> f64 {
+    match find_bracket(payments, convention) {
+        Some((a, b)) => brent_in_bracket(payments, a, b, convention),
+        None => f64::NAN,
+    }
+}
+
+fn find_bracket<T: PaymentDate>(
+    payments: &Vec<&Payment<T>>,
+    convention: DayCount,
+) -> Option<(f64, f64)> {
+    let mut growth = MIN_GROWTH;
+    let mut fa = xirr(payments, growth - 1.0, convention);
+
+    while growth < MAX_GROWTH {
+        let next_growth = growth * GROWTH_STEP;
+        let fb = xirr(payments, next_growth - 1.0, convention);
+
+        if fa * fb < 0.0 {
+            return Some((growth - 1.0, next_growth - 1.0));
+        }
+
+        growth = next_growth;
+        fa = fb;
+    }
+
+    None
+}
+
+fn brent_in_bracket<T: PaymentDate>(
+    payments: &Vec<&Payment<T>>,
+    mut a: f64,
+    mut b: f64,
+    convention: DayCount,
+) -> f64 {
+    let mut fa = xirr(payments, a, convention);
+    let mut fb = xirr(payments, b, convention);
+
+    if fa.abs() < fb.abs() {
+        std::mem::swap(&mut a, &mut b);
+        std::mem::swap(&mut fa, &mut fb);
+    }
+
+    let mut c = a;
+    let mut fc = fa;
+    let mut d = a;
+    let mut mflag = true;
+
+    for _ in 0..MAX_BRENT_ITERATIONS {
+        if fb.abs() <= MAX_ERROR || (b - a).abs() <= MAX_ERROR {
+            return b;
+        }
+
+        let mut s = if fa != fc && fb != fc {
+            a * fb * fc / ((fa - fb) * (fa - fc))
+                + b * fa * fc / ((fb - fa) * (fb - fc))
+                + c * fa * fb / ((fc - fa) * (fc - fb))
+        } else {
+            b - fb * (b - a) / (fb - fa)
+        };
+
+        let out_of_bounds = (s - b) * (s - (3.0 * a + b) / 4.0) > 0.0;
+        let too_slow = if mflag {
+            (s - b).abs() >= (b - c).abs() / 2.0 || (b - c).abs() < MAX_ERROR
+        } else {
+            (s - b).abs() >= (c - d).abs() / 2.0 || (c - d).abs() < MAX_ERROR
+        };
+
+        if out_of_bounds || too_slow {
+            s = (a + b) / 2.0;
+            mflag = true;
+        } else {
+            mflag = false;
+        }
+
+        let fs = xirr(payments, s, convention);
+        d = c;
+        c = b;
+        fc = fb;
+
+        if fa * fs < 0.0 {
+            b = s;
+            fb = fs;
+        } else {
+            a = s;
+            fa = fs;
+        }
+
+        if fa.abs() < fb.abs() {
+            std::mem::swap(&mut a, &mut b);
+            std::mem::swap(&mut fa, &mut fb);
+        }
+    }
+
+    b
+}
+
 fn validate<T: PaymentDate>(payments: &Vec<Payment<T>>) -> Result<(), InvalidPaymentsError> {
     let positive = payments.iter().any(|p| p.amount > 0.0);
     let negative = payments.iter().any(|p| p.amount < 0.0);
@@ -144,15 +446,106 @@ fn validate<T: PaymentDate>(payments: &Vec<Payment<T>>) -> Result<(), InvalidPay
     }
 }
 
-fn get_exp<T: PaymentDate>(p: &Payment<T>, p0: &Payment<T>) -> f64 {
-    p.date.days_since(p0.date) as f64 / 365.0
+fn get_exp<T: PaymentDate>(p: &Payment<T>, p0: &Payment<T>, convention: DayCount) -> f64 {
+    match convention {
+        DayCount::Actual365Fixed => p.date.fractional_days_since(p0.date) / 365.0,
+        DayCount::Actual360 => p.date.fractional_days_since(p0.date) / 360.0,
+        DayCount::Thirty360 => thirty_360_days(p0.date, p.date) as f64 / 360.0,
+        DayCount::ActualActual => {
+            let days = p.date.fractional_days_since(p0.date);
+            let denom = if spans_leap_day(p0.date, p.date) { 366.0 } else { 365.0 };
+            days / denom
+        }
+    }
+}
+
+// The 30/360 convention treats every month as having 30 days. A start on the 31st is clamped down
+// to the 30th, and an end on the 31st is clamped to the 30th when the start is then on the 30th,
+// so that e.g. Jan 31 to Apr 30 is treated as exactly three months (90 days) rather than 89.
+fn thirty_360_days<T: PaymentDate>(d0: T, d1: T) -> i32 {
+    let (y1, m1, mut day1) = (d0.year(), d0.month(), d0.day());
+    let (y2, m2, mut day2) = (d1.year(), d1.month(), d1.day());
+
+    if day1 == 31 {
+        day1 = 30;
+    }
+    if day2 == 31 && day1 == 30 {
+        day2 = 30;
+    }
+
+    360 * (y2 - y1) + 30 * (m2 - m1) + (day2 - day1)
+}
+
+// Whether the (d0, d1] interval spans a Feb 29, used to pick the 365 vs. 366 denominator for the
+// actual/actual convention. Year/month/day tuples compare the same way the underlying dates do,
+// so this avoids needing to construct an intermediate date of type `T`.
+fn spans_leap_day<T: PaymentDate>(d0: T, d1: T) -> bool {
+    let start = (d0.year(), d0.month(), d0.day());
+    let end = (d1.year(), d1.month(), d1.day());
+
+    (d0.year()..=d1.year()).any(|year| {
+        is_leap_year(year) && {
+            let feb29 = (year, 2, 29);
+            feb29 > start && feb29 <= end
+        }
+    })
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+/// The day-count convention used to compute the year fraction between two payment dates.
+///
+/// [`compute`] and [`compute_with_guess`] use [`Actual365Fixed`](DayCount::Actual365Fixed),
+/// matching LibreOffice Calc's XIRR. Use [`compute_with_convention`] to select a different one.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DayCount {
+    /// Actual number of days between dates, divided by 365.
+    Actual365Fixed,
+    /// Actual number of days between dates, divided by 360.
+    Actual360,
+    /// 30 days per month, 360 days per year, with end-of-month clamping.
+    Thirty360,
+    /// Actual number of days between dates, divided by 365 or 366 depending on whether the
+    /// interval spans a leap day.
+    ActualActual,
 }
 
 /// A trait representing the date on which a payment was made.
 ///
-/// This trait is implemented for [`jiff::civil::Date`](::jiff::civil::Date)
-/// and [`chrono::NaiveDate`](::chrono::NaiveDate).
+/// This trait is implemented for [`jiff::civil::Date`](::jiff::civil::Date),
+/// [`jiff::civil::DateTime`](::jiff::civil::DateTime), [`chrono::NaiveDate`](::chrono::NaiveDate)
+/// and [`chrono::NaiveDateTime`](::chrono::NaiveDateTime).
 pub trait PaymentDate: Ord + Sized + Copy {
     /// Calculates the number days from the `other` date to this date.
     fn days_since(self, other: Self) -> i32;
+
+    /// The calendar year of this date.
+    fn year(self) -> i32;
+
+    /// The calendar month of this date, from 1 to 12.
+    fn month(self) -> i32;
+
+    /// The day of the month of this date, from 1 to 31.
+    fn day(self) -> i32;
+
+    /// Adds the given number of days to this date.
+    fn add_days(self, days: i32) -> Self;
+
+    /// Adds the given number of months to this date, clamping the day of the month to the last
+    /// valid day of the resulting month if necessary (e.g. Jan 31 + 1 month -> Feb 28/29).
+    fn checked_add_months(self, months: i32) -> Self;
+
+    /// The number of days from the `other` date to this date, including a fractional component
+    /// for types that carry a time of day.
+    ///
+    /// The default implementation simply widens [`days_since`](PaymentDate::days_since), which is
+    /// exact for date-only types. Types with sub-day precision (e.g.
+    /// [`jiff::civil::DateTime`](::jiff::civil::DateTime) or
+    /// [`chrono::NaiveDateTime`](::chrono::NaiveDateTime)) override this so that payments made on
+    /// the same calendar date but at different times still discount distinctly.
+    fn fractional_days_since(self, other: Self) -> f64 {
+        self.days_since(other) as f64
+    }
 }
@@ -1,4 +1,4 @@
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime};
 use xirr::*;
 
 const MAX_ERROR: f64 = 1e-10;
@@ -44,6 +44,198 @@ fn test_same_sign() {
     assert!(result_positive.is_err());
 }
 
+#[test]
+fn test_brent_fallback_on_newton_divergence() {
+    // A short gap between a large outlay and its redemption implies an annualized rate well
+    // outside the -99%..99% guess sweep compute's Newton step tries, which used to return NaN.
+    let payments = vec![
+        Payment {
+            date: "2020-01-01".parse().unwrap(),
+            amount: -6.1,
+        },
+        Payment {
+            date: "2020-01-02".parse().unwrap(),
+            amount: -13.0,
+        },
+        Payment {
+            date: "2020-01-03".parse().unwrap(),
+            amount: 6.6,
+        },
+    ];
+    let rate = compute::<NaiveDate>(&payments).unwrap();
+
+    assert!(rate.is_finite());
+    assert!(xnpv(&payments, rate).abs() <= 1e-6);
+}
+
+#[test]
+fn test_day_count_actual_360() {
+    // 2021-01-01 to 2021-12-27 is exactly 360 actual days, so Actual/360 treats it as exactly
+    // one year and should recover the 20% return exactly.
+    let payments = vec![
+        Payment {
+            date: "2021-01-01".parse().unwrap(),
+            amount: -100.0,
+        },
+        Payment {
+            date: "2021-12-27".parse().unwrap(),
+            amount: 120.0,
+        },
+    ];
+
+    let actual_360 = compute_with_convention::<NaiveDate>(&payments, DayCount::Actual360).unwrap();
+    assert!((actual_360 - 0.2).abs() <= MAX_ERROR);
+
+    // Actual/365 sees the same 360 days as slightly less than a year, implying a slightly
+    // higher annualized rate for the same growth.
+    let actual_365 =
+        compute_with_convention::<NaiveDate>(&payments, DayCount::Actual365Fixed).unwrap();
+    assert!(actual_365 > actual_360);
+}
+
+#[test]
+fn test_day_count_thirty_360_clamps_end_of_month() {
+    // Thirty/360 clamps Jan 31 down to the 30th, so it treats this span as exactly
+    // 90/360 = 0.25 years (three 30-day months), even though only 89 calendar days elapsed.
+    let payments = vec![
+        Payment {
+            date: "2021-01-31".parse().unwrap(),
+            amount: -100.0,
+        },
+        Payment {
+            date: "2021-04-30".parse().unwrap(),
+            amount: 106.0,
+        },
+    ];
+
+    let thirty_360 = compute_with_convention::<NaiveDate>(&payments, DayCount::Thirty360).unwrap();
+    let actual_365 =
+        compute_with_convention::<NaiveDate>(&payments, DayCount::Actual365Fixed).unwrap();
+
+    // Actual/365 sees a shorter period (89 days) for the same growth, implying a higher rate.
+    assert!(actual_365 > thirty_360);
+}
+
+#[test]
+fn test_day_count_actual_actual_leap_day() {
+    // 2020-01-01 to 2021-01-01 spans Feb 29, 2020 (a leap year), so actual/actual divides by
+    // 366 and sees exactly one year, recovering the 20% return exactly.
+    let payments = vec![
+        Payment {
+            date: "2020-01-01".parse().unwrap(),
+            amount: -100.0,
+        },
+        Payment {
+            date: "2021-01-01".parse().unwrap(),
+            amount: 120.0,
+        },
+    ];
+
+    let actual_actual =
+        compute_with_convention::<NaiveDate>(&payments, DayCount::ActualActual).unwrap();
+    assert!((actual_actual - 0.2).abs() <= MAX_ERROR);
+
+    // Actual/365 always divides by 365, so it sees slightly more than a year, implying a
+    // slightly lower annualized rate for the same growth.
+    let actual_365 =
+        compute_with_convention::<NaiveDate>(&payments, DayCount::Actual365Fixed).unwrap();
+    assert!(actual_365 < actual_actual);
+}
+
+#[test]
+fn test_mirr_two_flows() {
+    // With only two flows, at the very first and very last dates, neither the reinvestment nor
+    // the financing rate has anything to act on, so MIRR reduces to a plain compounded return.
+    // 2021-01-01 to 2022-01-01 is exactly 365 days (2021 is not a leap year), so this recovers
+    // the 30% return exactly regardless of the rates passed in.
+    let payments = vec![
+        Payment {
+            date: "2021-01-01".parse().unwrap(),
+            amount: -100.0,
+        },
+        Payment {
+            date: "2022-01-01".parse().unwrap(),
+            amount: 130.0,
+        },
+    ];
+
+    let rate = mirr::<NaiveDate>(&payments, 0.05, 0.08).unwrap();
+    assert!((rate - 0.3).abs() <= MAX_ERROR);
+}
+
+#[test]
+fn test_mirr_same_sign_errors() {
+    let payments = vec![
+        Payment {
+            date: "2021-01-01".parse().unwrap(),
+            amount: -100.0,
+        },
+        Payment {
+            date: "2022-01-01".parse().unwrap(),
+            amount: -50.0,
+        },
+    ];
+
+    assert!(mirr::<NaiveDate>(&payments, 0.05, 0.08).is_err());
+}
+
+#[test]
+fn test_mirr_same_date_errors() {
+    let payments = vec![
+        Payment {
+            date: "2021-01-01".parse().unwrap(),
+            amount: -100.0,
+        },
+        Payment {
+            date: "2021-01-01".parse().unwrap(),
+            amount: 100.0,
+        },
+    ];
+
+    assert!(mirr::<NaiveDate>(&payments, 0.05, 0.08).is_err());
+}
+
+#[test]
+fn test_generate_schedule_count_and_month_clamp() {
+    let start: NaiveDate = "2021-01-31".parse().unwrap();
+    let schedule = generate_schedule(start, -100.0, Frequency::Monthly, 1, ScheduleEnd::Count(3));
+
+    let dates: Vec<NaiveDate> = schedule.iter().map(|p| p.date).collect();
+    assert_eq!(
+        vec![
+            "2021-01-31".parse::<NaiveDate>().unwrap(),
+            "2021-02-28".parse::<NaiveDate>().unwrap(),
+            "2021-03-28".parse::<NaiveDate>().unwrap(),
+        ],
+        dates
+    );
+    assert!(schedule.iter().all(|p| p.amount == -100.0));
+}
+
+#[test]
+fn test_generate_schedule_end_date() {
+    let start: NaiveDate = "2021-01-01".parse().unwrap();
+    let end: NaiveDate = "2021-03-01".parse().unwrap();
+    let schedule = generate_schedule(start, -50.0, Frequency::Monthly, 1, ScheduleEnd::Date(end));
+
+    let dates: Vec<NaiveDate> = schedule.iter().map(|p| p.date).collect();
+    assert_eq!(
+        vec![
+            "2021-01-01".parse::<NaiveDate>().unwrap(),
+            "2021-02-01".parse::<NaiveDate>().unwrap(),
+            "2021-03-01".parse::<NaiveDate>().unwrap(),
+        ],
+        dates
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_generate_schedule_rejects_zero_interval() {
+    let start: NaiveDate = "2021-01-01".parse().unwrap();
+    generate_schedule(start, -50.0, Frequency::Monthly, 0, ScheduleEnd::Count(1));
+}
+
 #[test]
 fn test_max_iter() {
     let payments = vec![
@@ -80,6 +272,72 @@ fn test_max_iter() {
     assert!(result.is_nan())
 }
 
+#[test]
+fn test_naive_date_time_resolves_same_day_flows() {
+    // Both outflows fall on the same calendar date but at different times. Collapsed onto
+    // `NaiveDate`, they'd share a single exponent like `test_max_iter`'s flows; `NaiveDateTime`'s
+    // fractional day count keeps them distinct instead, and the rate still converges.
+    let payments = vec![
+        Payment {
+            date: "2020-01-01T08:00:00".parse::<NaiveDateTime>().unwrap(),
+            amount: -10000.0,
+        },
+        Payment {
+            date: "2020-01-01T16:00:00".parse::<NaiveDateTime>().unwrap(),
+            amount: -2000.0,
+        },
+        Payment {
+            date: "2021-01-01T00:00:00".parse::<NaiveDateTime>().unwrap(),
+            amount: 13500.0,
+        },
+    ];
+
+    let result = compute(&payments).unwrap();
+    assert!(!result.is_nan());
+}
+
+#[test]
+fn test_naive_date_time_distinguishes_intraday_flows() {
+    // Identical cash flows except for the time of day of the middle payment. The half-day
+    // difference in how long that payment is discounted should nudge the two computed rates
+    // apart, even though both land on the same calendar dates.
+    let earlier = vec![
+        Payment {
+            date: "2020-01-01T00:00:00".parse::<NaiveDateTime>().unwrap(),
+            amount: -10000.0,
+        },
+        Payment {
+            date: "2020-06-15T06:00:00".parse::<NaiveDateTime>().unwrap(),
+            amount: 2000.0,
+        },
+        Payment {
+            date: "2021-01-01T00:00:00".parse::<NaiveDateTime>().unwrap(),
+            amount: 9000.0,
+        },
+    ];
+    let later = vec![
+        Payment {
+            date: "2020-01-01T00:00:00".parse::<NaiveDateTime>().unwrap(),
+            amount: -10000.0,
+        },
+        Payment {
+            date: "2020-06-15T18:00:00".parse::<NaiveDateTime>().unwrap(),
+            amount: 2000.0,
+        },
+        Payment {
+            date: "2021-01-01T00:00:00".parse::<NaiveDateTime>().unwrap(),
+            amount: 9000.0,
+        },
+    ];
+
+    let rate_earlier = compute(&earlier).unwrap();
+    let rate_later = compute(&later).unwrap();
+
+    assert!(rate_earlier.is_finite());
+    assert!(rate_later.is_finite());
+    assert!((rate_earlier - rate_later).abs() > 1e-9);
+}
+
 fn load_payments(file: &str) -> Vec<Payment<NaiveDate>> {
     csv::ReaderBuilder::new()
         .has_headers(false)